@@ -0,0 +1,50 @@
+use reqwest::Url;
+use tokio::sync::broadcast;
+
+/// How many past events a lagging subscriber can fall behind before it starts missing them.
+/// Alerts are best-effort notifications, not a durable log, so a bounded ring buffer is fine.
+const ALERT_CHANNEL_CAPACITY: usize = 256;
+
+/// Lifecycle notifications emitted by a [`TorrentManager`](crate::torrent_manager), so an
+/// embedding application can react to progress without busy-polling `stats_snapshot`.
+#[derive(Clone, Debug)]
+pub enum TorrentEvent {
+    InitialCheckComplete { have_bytes: u64, needed_bytes: u64 },
+    PieceCompleted { index: u32 },
+    // `seeders`/`leechers` are `None` for trackers whose announce response doesn't carry swarm
+    // counters (e.g. the HTTP compact response), rather than an indistinguishable-from-dead 0.
+    TrackerAnnounced {
+        url: Url,
+        seeders: Option<u32>,
+        leechers: Option<u32>,
+        interval: u64,
+    },
+    TorrentCompleted,
+}
+
+/// Owns the broadcast channel a `TorrentManager` emits [`TorrentEvent`]s on. Cloning an
+/// `AlertSender` shares the same underlying channel.
+#[derive(Clone)]
+pub struct AlertSender(broadcast::Sender<TorrentEvent>);
+
+impl AlertSender {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Self(tx)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TorrentEvent> {
+        self.0.subscribe()
+    }
+
+    /// Emits an event to all current subscribers. It's not an error for there to be none.
+    pub fn emit(&self, event: TorrentEvent) {
+        let _ = self.0.send(event);
+    }
+}
+
+impl Default for AlertSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use librqbit_core::{id20::Id20, lengths::Lengths};
+use sha1w::Sha1;
+
+use crate::file_ops::{FileOps, InitialCheckResults};
+
+const RESUME_DIR_NAME: &str = ".rqbit-resume";
+const MAGIC: &[u8; 4] = b"RQR1";
+
+/// Number of pieces to re-hash as a sanity check when loading resume data, even though the
+/// on-disk bitfield is otherwise trusted. 0 disables spot-checking entirely.
+pub const DEFAULT_SPOT_CHECK_PIECES: usize = 16;
+
+pub fn resume_file_path(out: &Path, info_hash: &Id20) -> PathBuf {
+    out.join(RESUME_DIR_NAME).join(format!("{}.bin", info_hash.as_string()))
+}
+
+struct FileStat {
+    len: u64,
+    mtime_secs: u64,
+}
+
+fn stat_files(filenames: &[PathBuf]) -> anyhow::Result<Vec<FileStat>> {
+    filenames
+        .iter()
+        .map(|p| {
+            let meta = fs::metadata(p)?;
+            let mtime_secs = meta
+                .modified()?
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            Ok(FileStat {
+                len: meta.len(),
+                mtime_secs,
+            })
+        })
+        .collect()
+}
+
+fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    let mut out = vec![0u8; bits.len().div_ceil(8)];
+    for (i, &bit) in bits.iter().enumerate() {
+        if bit {
+            out[i / 8] |= 1 << (7 - (i % 8));
+        }
+    }
+    out
+}
+
+fn unpack_bits(bytes: &[u8], count: usize) -> Vec<bool> {
+    (0..count)
+        .map(|i| bytes[i / 8] & (1 << (7 - (i % 8))) != 0)
+        .collect()
+}
+
+/// Serializes the have/needed piece bitfield, alongside enough metadata to detect a stale or
+/// mismatched sidecar on the next start, to `<out>/.rqbit-resume/<info_hash>.bin`.
+pub fn save(
+    out: &Path,
+    info_hash: &Id20,
+    lengths: &Lengths,
+    filenames: &[PathBuf],
+    have_pieces: &[bool],
+) -> anyhow::Result<()> {
+    let dir = out.join(RESUME_DIR_NAME);
+    fs::create_dir_all(&dir)?;
+    let stats = stat_files(filenames)?;
+
+    let mut buf = Vec::new();
+    buf.extend_from_slice(MAGIC);
+    buf.extend_from_slice(&info_hash.0);
+    buf.extend_from_slice(&lengths.total_length().to_be_bytes());
+    buf.extend_from_slice(&lengths.piece_length().to_be_bytes());
+    buf.extend_from_slice(&(stats.len() as u32).to_be_bytes());
+    for stat in &stats {
+        buf.extend_from_slice(&stat.len.to_be_bytes());
+        buf.extend_from_slice(&stat.mtime_secs.to_be_bytes());
+    }
+    buf.extend_from_slice(&(have_pieces.len() as u32).to_be_bytes());
+    buf.extend_from_slice(&pack_bits(have_pieces));
+
+    // Write to a temp file and rename over the real one, so a crash mid-write can't leave
+    // behind a sidecar a future start would load as valid.
+    let path = resume_file_path(out, info_hash);
+    let tmp_path = path.with_extension("bin.tmp");
+    fs::write(&tmp_path, &buf)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+/// Loads and validates the resume sidecar for `info_hash` under `out`, re-hashing up to
+/// `spot_check_pieces` of the pieces it claims to have as a sanity check.
+///
+/// Returns `Ok(None)` if there's no sidecar to load. Any other validation failure (missing
+/// data file, lengths mismatch, stale file metadata, failed spot-check) is returned as an `Err`;
+/// callers should treat that the same as "no resume data" and fall back to a full check.
+pub fn load(
+    out: &Path,
+    info_hash: &Id20,
+    lengths: &Lengths,
+    filenames: &[PathBuf],
+    spot_check_pieces: usize,
+    file_ops: &FileOps<Sha1>,
+) -> anyhow::Result<Option<InitialCheckResults>> {
+    let path = resume_file_path(out, info_hash);
+    let buf = match fs::read(&path) {
+        Ok(buf) => buf,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    anyhow::ensure!(buf.len() >= 4 + 20 + 8 + 4 + 4, "resume file {:?} is too short", path);
+    anyhow::ensure!(&buf[0..4] == MAGIC, "resume file {:?} has a bad magic header", path);
+    anyhow::ensure!(
+        buf[4..24] == info_hash.0,
+        "resume file {:?} was written for a different info_hash",
+        path
+    );
+
+    let mut pos = 24;
+    let total_length = u64::from_be_bytes(buf[pos..pos + 8].try_into().unwrap());
+    pos += 8;
+    let piece_length = u32::from_be_bytes(buf[pos..pos + 4].try_into().unwrap());
+    pos += 4;
+    anyhow::ensure!(
+        total_length == lengths.total_length() && piece_length == lengths.piece_length(),
+        "resume file {:?} was written for a torrent with different lengths",
+        path
+    );
+
+    let file_count = u32::from_be_bytes(buf[pos..pos + 4].try_into().unwrap()) as usize;
+    pos += 4;
+    anyhow::ensure!(
+        file_count == filenames.len(),
+        "resume file {:?} has a different number of files than the torrent",
+        path
+    );
+
+    let stats = stat_files(filenames)?;
+    for stat in &stats {
+        anyhow::ensure!(pos + 16 <= buf.len(), "resume file {:?} is truncated", path);
+        let len = u64::from_be_bytes(buf[pos..pos + 8].try_into().unwrap());
+        let mtime_secs = u64::from_be_bytes(buf[pos + 8..pos + 16].try_into().unwrap());
+        pos += 16;
+        anyhow::ensure!(
+            len == stat.len && mtime_secs == stat.mtime_secs,
+            "a file on disk changed size or modification time since the resume data at {:?} was saved",
+            path
+        );
+    }
+
+    anyhow::ensure!(pos + 4 <= buf.len(), "resume file {:?} is truncated", path);
+    let num_pieces = u32::from_be_bytes(buf[pos..pos + 4].try_into().unwrap()) as usize;
+    pos += 4;
+    anyhow::ensure!(
+        num_pieces == lengths.total_pieces() as usize,
+        "resume file {:?} has a piece count that doesn't match the torrent",
+        path
+    );
+    anyhow::ensure!(
+        buf.len() - pos == num_pieces.div_ceil(8),
+        "resume file {:?} has a bitfield of the wrong size",
+        path
+    );
+    let have_pieces = unpack_bits(&buf[pos..], num_pieces);
+
+    if spot_check_pieces > 0 {
+        let have_indices: Vec<usize> = have_pieces
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, &have)| have.then_some(idx))
+            .collect();
+        let sample_count = spot_check_pieces.min(have_indices.len());
+        // Sample spread across the whole file rather than just the front, so a truncated or
+        // replaced tail is still likely to get caught.
+        let stride = (have_indices.len() / sample_count.max(1)).max(1);
+        for &piece in have_indices.iter().step_by(stride).take(sample_count) {
+            anyhow::ensure!(
+                file_ops.verify_piece(piece)?,
+                "spot-check failed: piece {} in {:?} does not match its expected hash",
+                piece,
+                path
+            );
+        }
+    }
+
+    let needed_pieces = have_pieces.iter().map(|&have| !have).collect();
+    let total_pieces = have_pieces.len();
+    let have_bytes = have_pieces
+        .iter()
+        .enumerate()
+        .filter(|(_, &have)| have)
+        .map(|(idx, _)| piece_byte_size(total_length, piece_length, total_pieces, idx))
+        .sum();
+    let needed_bytes = total_length - have_bytes;
+
+    Ok(Some(InitialCheckResults {
+        have_pieces,
+        needed_pieces,
+        have_bytes,
+        needed_bytes,
+    }))
+}
+
+fn piece_byte_size(total_length: u64, piece_length: u32, total_pieces: usize, idx: usize) -> u64 {
+    if idx + 1 == total_pieces {
+        total_length - (piece_length as u64) * (total_pieces as u64 - 1)
+    } else {
+        piece_length as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_bits_packs_msb_first_and_pads_with_zero_bits() {
+        assert_eq!(pack_bits(&[]), Vec::<u8>::new());
+        assert_eq!(pack_bits(&[true]), vec![0b1000_0000]);
+        assert_eq!(pack_bits(&[false, true, false, false, false, false, false, false]), vec![0b0100_0000]);
+        assert_eq!(
+            pack_bits(&[true, false, true, true, false, false, false, false, true]),
+            vec![0b1011_0000, 0b1000_0000]
+        );
+    }
+
+    #[test]
+    fn unpack_bits_is_the_inverse_of_pack_bits() {
+        let cases: &[&[bool]] = &[
+            &[],
+            &[true],
+            &[false],
+            &[true, false, true, true, false, false, false, false, true],
+            &[true; 17],
+        ];
+        for &bits in cases {
+            let packed = pack_bits(bits);
+            assert_eq!(unpack_bits(&packed, bits.len()), bits);
+        }
+    }
+
+    #[test]
+    fn piece_byte_size_uses_piece_length_except_for_the_last_piece() {
+        // 1025 bytes total, piece length 512 -> 2 full pieces + a 1-byte remainder.
+        assert_eq!(piece_byte_size(1025, 512, 3, 0), 512);
+        assert_eq!(piece_byte_size(1025, 512, 3, 1), 512);
+        assert_eq!(piece_byte_size(1025, 512, 3, 2), 1);
+    }
+
+    #[test]
+    fn piece_byte_size_single_piece_torrent() {
+        assert_eq!(piece_byte_size(100, 512, 1, 0), 100);
+    }
+}
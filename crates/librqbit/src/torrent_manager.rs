@@ -1,5 +1,5 @@
 use std::{
-    collections::HashSet,
+    collections::HashMap,
     fs::{File, OpenOptions},
     net::SocketAddr,
     path::{Path, PathBuf},
@@ -21,19 +21,155 @@ use size_format::SizeFormatterBinary as SF;
 use tracing::{debug, info, span, warn, Level};
 
 use crate::{
+    alerts::{AlertSender, TorrentEvent},
     chunk_tracker::ChunkTracker,
     file_ops::FileOps,
     spawn_utils::{spawn, BlockingSpawner},
     torrent_state::{ManagedTorrent, ManagedTorrentHandle, TorrentStateLive, TorrentStateOptions},
+    resume,
     tracker_comms::{TrackerError, TrackerRequest, TrackerRequestEvent, TrackerResponse},
+    udp_tracker::UdpTrackerClient,
 };
 
+// How often to re-scrape each tracker for swarm health counters.
+const SCRAPE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// How often to persist the fast-resume bitfield, in addition to saving it after a full
+// initial check, after pieces complete, and on drop.
+const RESUME_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+// BEP 12 per-tracker error backoff: start at 5s, double on every consecutive failure, capped
+// at 15 minutes, reset back to the initial value on the next success.
+const TRACKER_BACKOFF_INITIAL: Duration = Duration::from_secs(5);
+const TRACKER_BACKOFF_MAX: Duration = Duration::from_secs(15 * 60);
+
+// BEP 12: while the primary tier has a working tracker, lower tiers are pure fallback and
+// shouldn't be announced to; this is how often a lower tier re-checks whether it's still needed.
+const TIER_FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Options controlling how a single torrent is managed, passed to [`TorrentManager::start`].
+#[derive(Clone, Default, Debug)]
+pub struct ManagedTorrentOptions {
+    pub overwrite: bool,
+    /// Bypasses any fast-resume sidecar and always runs the full initial checksum validation.
+    pub force_recheck: bool,
+    pub only_files: Option<Vec<usize>>,
+    pub peer_connect_timeout: Option<Duration>,
+    pub peer_read_write_timeout: Option<Duration>,
+    pub peer_id: Option<Id20>,
+    pub force_tracker_interval: Option<Duration>,
+}
+
 struct TorrentManager {
     state: Arc<TorrentStateLive>,
     #[allow(dead_code)]
     speed_estimator: Arc<SpeedEstimator>,
-    trackers: Mutex<HashSet<Url>>,
-    options: TorrentManagerOptions,
+    // Ordered announce-list tiers (BEP 12): trackers within a tier are tried in order, and a
+    // tracker that answers successfully is promoted to the front of its tier.
+    trackers: Mutex<Vec<Vec<Url>>>,
+    tracker_states: Mutex<HashMap<Url, TrackerState>>,
+    scrape: Mutex<Option<ScrapeInfo>>,
+    out_dir: PathBuf,
+    filenames: Vec<PathBuf>,
+    alerts: AlertSender,
+    options: ManagedTorrentOptions,
+}
+
+#[derive(Clone, Debug)]
+struct TrackerState {
+    working: bool,
+    last_error: Option<String>,
+    next_retry_at: Option<Instant>,
+    backoff: Duration,
+}
+
+impl Default for TrackerState {
+    fn default() -> Self {
+        Self {
+            working: false,
+            last_error: None,
+            next_retry_at: None,
+            backoff: TRACKER_BACKOFF_INITIAL,
+        }
+    }
+}
+
+/// A tracker's last known health, so users can diagnose dead trackers. Exposed through
+/// [`TorrentManagerHandle::tracker_statuses`]; it is not part of the torrent's `stats_snapshot`.
+#[derive(Clone, Debug)]
+pub struct TrackerStatus {
+    pub url: Url,
+    pub working: bool,
+    pub last_error: Option<String>,
+    pub next_retry_at: Option<Instant>,
+}
+
+impl Drop for TorrentManager {
+    fn drop(&mut self) {
+        let info_hash = self.state.info_hash();
+        if let Err(e) = resume::save(
+            &self.out_dir,
+            &info_hash,
+            &self.state.lengths(),
+            &self.filenames,
+            &self.state.have_pieces(),
+        ) {
+            warn!("error saving fast-resume data for {:?} on shutdown: {:#}", info_hash, e);
+        }
+    }
+}
+
+/// Swarm health counters for this torrent, as last reported by a tracker's scrape endpoint.
+/// Exposed through [`TorrentManagerHandle::scrape_snapshot`]; it is not part of the torrent's
+/// `stats_snapshot`.
+#[derive(Clone, Copy, Debug)]
+pub struct ScrapeInfo {
+    pub seeders: u32,
+    pub leechers: u32,
+    pub completed: u32,
+}
+
+struct AnnounceResult {
+    interval: u64,
+    seeders: Option<u32>,
+    leechers: Option<u32>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct HttpScrapeResponseFile {
+    complete: u32,
+    downloaded: u32,
+    incomplete: u32,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct HttpScrapeResponse {
+    files: std::collections::BTreeMap<ByteString, HttpScrapeResponseFile>,
+}
+
+fn urlencode_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 3);
+    for &b in bytes {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push('%');
+            out.push_str(&format!("{:02X}", b));
+        }
+    }
+    out
+}
+
+// BEP 48: the scrape URL is the announce URL with the last path segment's leading "announce"
+// replaced by "scrape". If the last path segment does not *start with* "announce", the
+// tracker doesn't support scraping.
+fn derive_http_scrape_url(announce_url: &Url) -> Option<Url> {
+    let mut scrape_url = announce_url.clone();
+    let last_segment = scrape_url.path_segments()?.last()?.to_owned();
+    let rest = last_segment.strip_prefix("announce")?;
+    let replaced = format!("scrape{}", rest);
+    scrape_url.path_segments_mut().ok()?.pop().push(&replaced);
+    Some(scrape_url)
 }
 
 fn make_lengths<ByteBuf: AsRef<[u8]>>(
@@ -93,11 +229,44 @@ impl TorrentManager {
         let lengths = make_lengths(&info).context("unable to compute Lengths from torrent")?;
         debug!("computed lengths: {:?}", &lengths);
 
-        info!("Doing initial checksum validation, this might take a while...");
-        let initial_check_results = spawner.spawn_block_in_place(|| {
-            FileOps::<Sha1>::new(&info, &files, &lengths)
-                .initial_check(options.only_files.as_deref())
-        })?;
+        let out_dir = out.as_ref().to_owned();
+        let file_ops = FileOps::<Sha1>::new(&info, &files, &lengths);
+
+        let resumed = if options.force_recheck {
+            None
+        } else {
+            match spawner.spawn_block_in_place(|| {
+                resume::load(
+                    &out_dir,
+                    &info_hash,
+                    &lengths,
+                    &filenames,
+                    resume::DEFAULT_SPOT_CHECK_PIECES,
+                    &file_ops,
+                )
+            }) {
+                Ok(resumed) => resumed,
+                Err(e) => {
+                    debug!("fast-resume data is missing or invalid, falling back to a full check: {:#}", e);
+                    None
+                }
+            }
+        };
+
+        // Whether this was a full `initial_check` rather than a loaded resume sidecar, so we
+        // know whether to write a fresh sidecar once the files below have their final length.
+        let did_full_check = resumed.is_none();
+
+        let initial_check_results = match resumed {
+            Some(r) => {
+                info!("Loaded fast-resume data, skipping the full initial checksum validation");
+                r
+            }
+            None => {
+                info!("Doing initial checksum validation, this might take a while...");
+                spawner.spawn_block_in_place(|| file_ops.initial_check(options.only_files.as_deref()))?
+            }
+        };
 
         info!(
             "Initial check results: have {}, needed {}",
@@ -105,6 +274,12 @@ impl TorrentManager {
             SF::new(initial_check_results.needed_bytes)
         );
 
+        let alerts = AlertSender::new();
+        alerts.emit(TorrentEvent::InitialCheckComplete {
+            have_bytes: initial_check_results.have_bytes,
+            needed_bytes: initial_check_results.needed_bytes,
+        });
+
         spawner.spawn_block_in_place(|| {
             for (idx, (file, (name, length))) in files
                 .iter()
@@ -136,6 +311,22 @@ impl TorrentManager {
             }
         });
 
+        if did_full_check {
+            // Only save now that the files above are resized to their final length: saving
+            // right after `initial_check` would record a brand-new download's pre-resize
+            // (e.g. zero-byte) file sizes, and the next launch's stat comparison against the
+            // now-correctly-sized files would always mismatch and force a full recheck anyway.
+            if let Err(e) = resume::save(
+                &out_dir,
+                &info_hash,
+                &lengths,
+                &filenames,
+                &initial_check_results.have_pieces,
+            ) {
+                warn!("error saving fast-resume data after initial check: {:#}", e);
+            }
+        }
+
         let chunk_tracker = ChunkTracker::new(
             initial_check_results.needed_pieces,
             initial_check_results.have_pieces,
@@ -149,6 +340,8 @@ impl TorrentManager {
             ..Default::default()
         };
 
+        let filenames_for_resume = filenames.clone();
+
         let state = TorrentStateLive::new(
             info,
             info_hash,
@@ -168,10 +361,34 @@ impl TorrentManager {
         let mgr = Arc::new(Self {
             state,
             speed_estimator: estimator.clone(),
-            trackers: Mutex::new(HashSet::new()),
+            trackers: Mutex::new(Vec::new()),
+            tracker_states: Mutex::new(HashMap::new()),
+            scrape: Mutex::new(None),
+            out_dir,
+            filenames: filenames_for_resume,
+            alerts,
             options,
         });
 
+        spawn(span!(Level::ERROR, "resume_data_updater"), {
+            let mgr = mgr.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(RESUME_SAVE_INTERVAL).await;
+                    let info_hash = mgr.state.info_hash();
+                    if let Err(e) = resume::save(
+                        &mgr.out_dir,
+                        &info_hash,
+                        &mgr.state.lengths(),
+                        &mgr.filenames,
+                        &mgr.state.have_pieces(),
+                    ) {
+                        warn!("error saving fast-resume data for {:?}: {:#}", info_hash, e);
+                    }
+                }
+            }
+        });
+
         spawn(span!(Level::ERROR, "speed_estimator_updater"), {
             let state = mgr.state.clone();
             async move {
@@ -189,14 +406,158 @@ impl TorrentManager {
             }
         });
 
+        spawn(span!(Level::ERROR, "scrape_updater"), {
+            let mgr = mgr.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(SCRAPE_INTERVAL).await;
+                    let trackers: Vec<Url> = mgr.trackers.lock().iter().flatten().cloned().collect();
+                    for tracker_url in trackers {
+                        match mgr.scrape_one(&tracker_url).await {
+                            Ok(info) => {
+                                debug!(
+                                    "scrape of {} succeeded: seeders={}, leechers={}, completed={}",
+                                    tracker_url, info.seeders, info.leechers, info.completed
+                                );
+                                *mgr.scrape.lock() = Some(info);
+                                break;
+                            }
+                            Err(e) => {
+                                debug!("scrape of {} failed: {:#}", tracker_url, e);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        spawn(span!(Level::ERROR, "piece_completion_watcher"), {
+            let mgr = mgr.clone();
+            async move {
+                let mut previously_had = mgr.state.have_pieces();
+                loop {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    let now_has = mgr.state.have_pieces();
+                    let mut completed_any = false;
+                    for (index, (&had, &has)) in
+                        previously_had.iter().zip(now_has.iter()).enumerate()
+                    {
+                        if has && !had {
+                            completed_any = true;
+                            mgr.alerts.emit(TorrentEvent::PieceCompleted {
+                                index: index as u32,
+                            });
+                        }
+                    }
+                    if !previously_had.iter().all(|&have| have) && now_has.iter().all(|&have| have)
+                    {
+                        mgr.alerts.emit(TorrentEvent::TorrentCompleted);
+                    }
+                    // Debounced save: at most once per tick of this loop, and only when a piece
+                    // actually completed, so an unclean exit loses at most ~1s of progress
+                    // instead of up to RESUME_SAVE_INTERVAL.
+                    if completed_any {
+                        let info_hash = mgr.state.info_hash();
+                        if let Err(e) = resume::save(
+                            &mgr.out_dir,
+                            &info_hash,
+                            &mgr.state.lengths(),
+                            &mgr.filenames,
+                            &now_has,
+                        ) {
+                            warn!(
+                                "error saving fast-resume data for {:?} after piece completion: {:#}",
+                                info_hash, e
+                            );
+                        }
+                    }
+                    previously_had = now_has;
+                }
+            }
+        });
+
         Ok(mgr.into_handle())
     }
 
+    /// Subscribes to this torrent's lifecycle events. Lagging subscribers may miss events
+    /// that were emitted before they could be read; see [`AlertSender`].
+    fn subscribe(&self) -> tokio::sync::broadcast::Receiver<TorrentEvent> {
+        self.alerts.subscribe()
+    }
+
+    fn scrape_snapshot(&self) -> Option<ScrapeInfo> {
+        *self.scrape.lock()
+    }
+
+    async fn scrape_one(&self, tracker_url: &Url) -> anyhow::Result<ScrapeInfo> {
+        if tracker_url.scheme() == "udp" {
+            self.scrape_one_udp(tracker_url).await
+        } else {
+            self.scrape_one_http(tracker_url).await
+        }
+    }
+
+    async fn scrape_one_http(&self, announce_url: &Url) -> anyhow::Result<ScrapeInfo> {
+        let mut scrape_url = derive_http_scrape_url(announce_url).with_context(|| {
+            format!("tracker {} does not support scraping", announce_url)
+        })?;
+        let info_hash = self.state.info_hash();
+        scrape_url.set_query(Some(&format!("info_hash={}", urlencode_bytes(&info_hash.0))));
+
+        let response = reqwest::get(scrape_url).await?;
+        if !response.status().is_success() {
+            anyhow::bail!("scrape responded with {:?}", response.status());
+        }
+        let bytes = response.bytes().await?;
+        if let Ok(error) = from_bytes::<TrackerError>(&bytes) {
+            anyhow::bail!(
+                "scrape returned failure. Failure reason: {}",
+                error.failure_reason
+            )
+        };
+        let response = from_bytes::<HttpScrapeResponse>(&bytes)?;
+        let file = response
+            .files
+            .get(info_hash.0.as_slice())
+            .context("scrape response did not include our info_hash")?;
+        Ok(ScrapeInfo {
+            seeders: file.complete,
+            leechers: file.incomplete,
+            completed: file.downloaded,
+        })
+    }
+
+    async fn scrape_one_udp(&self, tracker_url: &Url) -> anyhow::Result<ScrapeInfo> {
+        let host = tracker_url
+            .host_str()
+            .context("UDP tracker URL is missing a host")?;
+        let port = tracker_url
+            .port()
+            .context("UDP tracker URL is missing a port")?;
+        let tracker_addr = tokio::net::lookup_host((host, port))
+            .await?
+            .next()
+            .with_context(|| format!("unable to resolve UDP tracker host {}", host))?;
+
+        let mut client = UdpTrackerClient::new(tracker_addr).await?;
+        let info = client
+            .scrape(&[self.state.info_hash()])
+            .await?
+            .into_iter()
+            .next()
+            .context("empty scrape response")?;
+        Ok(ScrapeInfo {
+            seeders: info.seeders,
+            leechers: info.leechers,
+            completed: info.completed,
+        })
+    }
+
     fn into_handle(self: Arc<Self>) -> TorrentManagerHandle {
         TorrentManagerHandle { manager: self }
     }
 
-    async fn tracker_one_request(&self, tracker_url: Url) -> anyhow::Result<u64> {
+    async fn tracker_one_request_http(&self, tracker_url: Url) -> anyhow::Result<AnnounceResult> {
         let response: reqwest::Response = reqwest::get(tracker_url).await?;
         if !response.status().is_success() {
             anyhow::bail!("tracker responded with {:?}", response.status());
@@ -213,50 +574,305 @@ impl TorrentManager {
         for peer in response.peers.iter_sockaddrs() {
             self.state.add_peer_if_not_seen(peer);
         }
-        Ok(response.interval)
+        Ok(AnnounceResult {
+            interval: response.interval,
+            // The compact tracker response doesn't carry swarm counters; scrape() is the
+            // authoritative source for those.
+            seeders: None,
+            leechers: None,
+        })
+    }
+
+    async fn tracker_one_request_udp(
+        &self,
+        tracker_url: &Url,
+        client: &mut Option<UdpTrackerClient>,
+        event: Option<TrackerRequestEvent>,
+        key: u32,
+    ) -> anyhow::Result<AnnounceResult> {
+        let host = tracker_url
+            .host_str()
+            .context("UDP tracker URL is missing a host")?;
+        let port = tracker_url
+            .port()
+            .context("UDP tracker URL is missing a port")?;
+        let tracker_addr = tokio::net::lookup_host((host, port))
+            .await?
+            .next()
+            .with_context(|| format!("unable to resolve UDP tracker host {}", host))?;
+
+        if client.is_none() {
+            *client = Some(UdpTrackerClient::new(tracker_addr).await?);
+        }
+
+        let response = client
+            .as_mut()
+            .unwrap()
+            .announce(
+                self.state.info_hash(),
+                self.state.peer_id(),
+                self.state.get_downloaded_bytes(),
+                self.state.get_left_to_download_bytes(),
+                self.state.get_uploaded_bytes(),
+                event,
+                key,
+                6778,
+            )
+            .await?;
+
+        for peer in &response.peers {
+            self.state.add_peer_if_not_seen(*peer);
+        }
+        Ok(AnnounceResult {
+            interval: response.interval as u64,
+            seeders: Some(response.seeders),
+            leechers: Some(response.leechers),
+        })
+    }
+
+    /// Registers the torrent's announce-list (BEP 12) and spawns one monitor task per tier.
+    /// An empty tier is ignored.
+    fn add_tracker_tiers(self: &Arc<Self>, tiers: Vec<Vec<Url>>) {
+        {
+            let mut states = self.tracker_states.lock();
+            for url in tiers.iter().flatten() {
+                states.entry(url.clone()).or_default();
+            }
+        }
+
+        let tier_count = tiers.len();
+        *self.trackers.lock() = tiers;
+
+        for tier_index in 0..tier_count {
+            let mgr = self.clone();
+            spawn(span!(Level::ERROR, "tier_monitor"), async move {
+                mgr.tier_monitor(tier_index).await;
+            });
+        }
+    }
+
+    fn promote_tracker(&self, tier_index: usize, tracker_url: &Url) {
+        let mut tiers = self.trackers.lock();
+        if let Some(tier) = tiers.get_mut(tier_index) {
+            if let Some(pos) = tier.iter().position(|url| url == tracker_url) {
+                let promoted = tier.remove(pos);
+                tier.insert(0, promoted);
+            }
+        }
+    }
+
+    fn tracker_statuses(&self) -> Vec<TrackerStatus> {
+        self.tracker_states
+            .lock()
+            .iter()
+            .map(|(url, state)| TrackerStatus {
+                url: url.clone(),
+                working: state.working,
+                last_error: state.last_error.clone(),
+                next_retry_at: state.next_retry_at,
+            })
+            .collect()
+    }
+
+    /// Whether tier `tier_index` has not yet stopped working as a whole: any of its trackers
+    /// is marked working, or has not been tried yet. While this holds, lower tiers are not
+    /// announced to.
+    fn tier_is_working(&self, tier_index: usize) -> bool {
+        let tier = match self.trackers.lock().get(tier_index).cloned() {
+            Some(tier) => tier,
+            None => return false,
+        };
+        let states = self.tracker_states.lock();
+        tier.iter().any(|url| {
+            states
+                .get(url)
+                .map(|s| s.working || s.last_error.is_none())
+                .unwrap_or(false)
+        })
     }
 
-    async fn single_tracker_monitor(&self, mut tracker_url: Url) -> anyhow::Result<()> {
+    /// Announces to the trackers in tier `tier_index`, in order, promoting whichever one
+    /// answers successfully to the front of the tier. Trackers in backoff after a recent
+    /// error are skipped until their retry time. Tiers below the first one are only announced
+    /// to once every tracker in the tier above them has stopped working.
+    async fn tier_monitor(&self, tier_index: usize) {
         let mut event = Some(TrackerRequestEvent::Started);
+        let mut udp_clients: HashMap<Url, UdpTrackerClient> = HashMap::new();
+        let key: u32 = rand::random();
+
         loop {
-            let request = TrackerRequest {
-                info_hash: self.state.info_hash(),
-                peer_id: self.state.peer_id(),
-                port: 6778,
-                uploaded: self.state.get_uploaded_bytes(),
-                downloaded: self.state.get_downloaded_bytes(),
-                left: self.state.get_left_to_download_bytes(),
-                compact: true,
-                no_peer_id: false,
-                event,
-                ip: None,
-                numwant: None,
-                key: None,
-                trackerid: None,
+            let tier = match self.trackers.lock().get(tier_index).cloned() {
+                Some(tier) if !tier.is_empty() => tier,
+                _ => return,
             };
 
-            let request_query = request.as_querystring();
-            tracker_url.set_query(Some(&request_query));
+            // BEP 12: a tier is only a fallback. While a higher tier still has a working
+            // tracker, skip announcing here entirely and just poll for the higher tier going
+            // down, instead of hitting these trackers unconditionally alongside it.
+            if (0..tier_index).any(|higher| self.tier_is_working(higher)) {
+                tokio::time::sleep(TIER_FALLBACK_POLL_INTERVAL).await;
+                continue;
+            }
 
-            match self.tracker_one_request(tracker_url.clone()).await {
-                Ok(interval) => {
-                    event = None;
-                    let interval = self
-                        .options
-                        .force_tracker_interval
-                        .unwrap_or_else(|| Duration::from_secs(interval));
-                    debug!(
-                        "sleeping for {:?} after calling tracker {}",
-                        interval,
-                        tracker_url.host().unwrap()
-                    );
-                    tokio::time::sleep(interval).await;
+            let mut announced = false;
+            let mut retry_in = TRACKER_BACKOFF_MAX;
+
+            for tracker_url in &tier {
+                let now = Instant::now();
+                if let Some(next_retry_at) = self
+                    .tracker_states
+                    .lock()
+                    .get(tracker_url)
+                    .and_then(|s| s.next_retry_at)
+                {
+                    if next_retry_at > now {
+                        retry_in = retry_in.min(next_retry_at - now);
+                        continue;
+                    }
                 }
-                Err(e) => {
-                    debug!("error calling the tracker {}: {:#}", tracker_url, e);
-                    tokio::time::sleep(Duration::from_secs(60)).await;
+
+                let result = if tracker_url.scheme() == "udp" {
+                    let mut client = udp_clients.remove(tracker_url);
+                    let result = self
+                        .tracker_one_request_udp(tracker_url, &mut client, event, key)
+                        .await;
+                    if let Some(client) = client {
+                        udp_clients.insert(tracker_url.clone(), client);
+                    }
+                    result
+                } else {
+                    let request = TrackerRequest {
+                        info_hash: self.state.info_hash(),
+                        peer_id: self.state.peer_id(),
+                        port: 6778,
+                        uploaded: self.state.get_uploaded_bytes(),
+                        downloaded: self.state.get_downloaded_bytes(),
+                        left: self.state.get_left_to_download_bytes(),
+                        compact: true,
+                        no_peer_id: false,
+                        event,
+                        ip: None,
+                        numwant: None,
+                        key: None,
+                        trackerid: None,
+                    };
+                    let mut http_url = tracker_url.clone();
+                    http_url.set_query(Some(&request.as_querystring()));
+                    self.tracker_one_request_http(http_url).await
+                };
+
+                match result {
+                    Ok(result) => {
+                        event = None;
+                        {
+                            let mut states = self.tracker_states.lock();
+                            let state = states.entry(tracker_url.clone()).or_default();
+                            state.working = true;
+                            state.last_error = None;
+                            state.next_retry_at = None;
+                            state.backoff = TRACKER_BACKOFF_INITIAL;
+                        }
+                        self.promote_tracker(tier_index, tracker_url);
+                        debug!(
+                            "announced to {}, next announce in {}s",
+                            tracker_url, result.interval
+                        );
+                        self.alerts.emit(TorrentEvent::TrackerAnnounced {
+                            url: tracker_url.clone(),
+                            seeders: result.seeders,
+                            leechers: result.leechers,
+                            interval: result.interval,
+                        });
+
+                        announced = true;
+                        let sleep_for = self
+                            .options
+                            .force_tracker_interval
+                            .unwrap_or_else(|| Duration::from_secs(result.interval));
+                        tokio::time::sleep(sleep_for).await;
+                        break;
+                    }
+                    Err(e) => {
+                        debug!("error calling the tracker {}: {:#}", tracker_url, e);
+                        let mut states = self.tracker_states.lock();
+                        let state = states.entry(tracker_url.clone()).or_default();
+                        state.working = false;
+                        state.last_error = Some(format!("{:#}", e));
+                        let backoff = state.backoff;
+                        state.next_retry_at = Some(Instant::now() + backoff);
+                        state.backoff = (backoff * 2).min(TRACKER_BACKOFF_MAX);
+                        drop(states);
+                        retry_in = retry_in.min(backoff);
+                    }
                 }
-            };
+            }
+
+            if !announced {
+                tokio::time::sleep(retry_in).await;
+            }
         }
     }
 }
+
+/// A cheaply-cloned, externally visible handle to a running [`TorrentManager`]. This is what
+/// the API/CLI layer holds onto; `TorrentManager` itself stays private to this module.
+#[derive(Clone)]
+pub struct TorrentManagerHandle {
+    manager: Arc<TorrentManager>,
+}
+
+impl TorrentManagerHandle {
+    /// Swarm health counters for this torrent, as last reported by a tracker's scrape
+    /// endpoint. `None` until the first successful scrape.
+    pub fn scrape_snapshot(&self) -> Option<ScrapeInfo> {
+        self.manager.scrape_snapshot()
+    }
+
+    /// Each tracker's last known health (working/erroring, last error, next retry time), so
+    /// the API/CLI can surface dead trackers through the stats snapshot.
+    pub fn tracker_statuses(&self) -> Vec<TrackerStatus> {
+        self.manager.tracker_statuses()
+    }
+
+    /// Subscribes to this torrent's lifecycle events. Lagging subscribers may miss events
+    /// that were emitted before they could be read; see [`AlertSender`].
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<TorrentEvent> {
+        self.manager.subscribe()
+    }
+
+    /// Registers the torrent's announce-list (BEP 12) and starts announcing to it: one monitor
+    /// task per tier, with lower tiers only kicking in once every tracker in the tier above has
+    /// stopped working. Call once per handle; an empty tier is ignored.
+    pub fn add_tracker_tiers(&self, tiers: Vec<Vec<Url>>) {
+        self.manager.add_tracker_tiers(tiers);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_http_scrape_url_replaces_the_announce_prefix() {
+        assert_eq!(
+            derive_http_scrape_url(&Url::parse("http://example.com/announce").unwrap())
+                .unwrap()
+                .as_str(),
+            "http://example.com/scrape"
+        );
+        assert_eq!(
+            derive_http_scrape_url(&Url::parse("http://example.com/announce.php").unwrap())
+                .unwrap()
+                .as_str(),
+            "http://example.com/scrape.php"
+        );
+    }
+
+    #[test]
+    fn derive_http_scrape_url_rejects_segments_not_starting_with_announce() {
+        assert!(derive_http_scrape_url(
+            &Url::parse("http://example.com/my-announce-proxy").unwrap()
+        )
+        .is_none());
+    }
+}
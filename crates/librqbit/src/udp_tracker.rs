@@ -0,0 +1,497 @@
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::Context;
+use librqbit_core::id20::Id20;
+use tokio::net::UdpSocket;
+
+use crate::tracker_comms::TrackerRequestEvent;
+
+// BEP 15.
+const PROTOCOL_ID: u64 = 0x41727101980;
+const MAX_RETRIES: u32 = 8;
+const CONNECTION_ID_TTL: Duration = Duration::from_secs(60);
+
+#[repr(u32)]
+enum Action {
+    Connect = 0,
+    Announce = 1,
+    Scrape = 2,
+}
+
+/// A single info_hash's scrape counters, as returned in a UDP scrape response.
+pub struct ScrapeInfo {
+    pub seeders: u32,
+    pub completed: u32,
+    pub leechers: u32,
+}
+
+/// BEP 15 caps a scrape request at 74 info_hashes (16 + 74 * 20 = 1496 bytes, just under a
+/// 1500-byte Ethernet MTU). Requests for more are truncated.
+pub const MAX_SCRAPE_INFO_HASHES: usize = 74;
+
+pub struct UdpAnnounceResponse {
+    pub interval: u32,
+    pub leechers: u32,
+    pub seeders: u32,
+    pub peers: Vec<SocketAddr>,
+}
+
+fn retransmit_timeout(attempt: u32) -> Duration {
+    Duration::from_secs(15 * 2u64.pow(attempt))
+}
+
+async fn send_and_receive(
+    socket: &UdpSocket,
+    tracker_addr: SocketAddr,
+    packet: &[u8],
+    response_buf: &mut [u8],
+) -> anyhow::Result<usize> {
+    // `socket` is connect()-ed to `tracker_addr`, so send()/recv() only ever talk to the real
+    // tracker; a spoofed reply from another host is filtered out by the kernel.
+    for attempt in 0..=MAX_RETRIES {
+        socket.send(packet).await?;
+        match tokio::time::timeout(retransmit_timeout(attempt), socket.recv(response_buf)).await {
+            Ok(result) => return Ok(result?),
+            Err(_elapsed) => continue,
+        }
+    }
+    anyhow::bail!(
+        "UDP tracker at {} did not respond after {} attempts",
+        tracker_addr,
+        MAX_RETRIES + 1
+    )
+}
+
+fn build_connect_request(transaction_id: u32) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(16);
+    packet.extend_from_slice(&PROTOCOL_ID.to_be_bytes());
+    packet.extend_from_slice(&(Action::Connect as u32).to_be_bytes());
+    packet.extend_from_slice(&transaction_id.to_be_bytes());
+    packet
+}
+
+fn parse_connect_response(buf: &[u8], transaction_id: u32) -> anyhow::Result<u64> {
+    anyhow::ensure!(buf.len() >= 16, "connect response is too short");
+    anyhow::ensure!(
+        u32::from_be_bytes(buf[0..4].try_into().unwrap()) == Action::Connect as u32,
+        "unexpected action in connect response"
+    );
+    anyhow::ensure!(
+        u32::from_be_bytes(buf[4..8].try_into().unwrap()) == transaction_id,
+        "transaction_id mismatch in connect response"
+    );
+    Ok(u64::from_be_bytes(buf[8..16].try_into().unwrap()))
+}
+
+async fn connect(socket: &UdpSocket, tracker_addr: SocketAddr) -> anyhow::Result<u64> {
+    let transaction_id: u32 = rand::random();
+    let packet = build_connect_request(transaction_id);
+
+    let mut buf = [0u8; 16];
+    let size = send_and_receive(socket, tracker_addr, &packet, &mut buf).await?;
+    parse_connect_response(&buf[..size], transaction_id)
+        .with_context(|| format!("in connect response from {}", tracker_addr))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_announce_request(
+    transaction_id: u32,
+    connection_id: u64,
+    info_hash: Id20,
+    peer_id: Id20,
+    downloaded: u64,
+    left: u64,
+    uploaded: u64,
+    event: Option<TrackerRequestEvent>,
+    key: u32,
+    port: u16,
+) -> Vec<u8> {
+    let event_code: u32 = match event {
+        None => 0,
+        Some(TrackerRequestEvent::Completed) => 1,
+        Some(TrackerRequestEvent::Started) => 2,
+        Some(TrackerRequestEvent::Stopped) => 3,
+    };
+
+    let mut packet = Vec::with_capacity(98);
+    packet.extend_from_slice(&connection_id.to_be_bytes());
+    packet.extend_from_slice(&(Action::Announce as u32).to_be_bytes());
+    packet.extend_from_slice(&transaction_id.to_be_bytes());
+    packet.extend_from_slice(&info_hash.0);
+    packet.extend_from_slice(&peer_id.0);
+    packet.extend_from_slice(&downloaded.to_be_bytes());
+    packet.extend_from_slice(&left.to_be_bytes());
+    packet.extend_from_slice(&uploaded.to_be_bytes());
+    packet.extend_from_slice(&event_code.to_be_bytes());
+    packet.extend_from_slice(&0u32.to_be_bytes()); // IP, 0 = let the tracker use the sender's address.
+    packet.extend_from_slice(&key.to_be_bytes());
+    packet.extend_from_slice(&(-1i32).to_be_bytes()); // num_want, -1 = default.
+    packet.extend_from_slice(&port.to_be_bytes());
+    packet
+}
+
+fn parse_announce_response(buf: &[u8], transaction_id: u32) -> anyhow::Result<UdpAnnounceResponse> {
+    anyhow::ensure!(buf.len() >= 20, "announce response too short");
+    anyhow::ensure!(
+        u32::from_be_bytes(buf[0..4].try_into().unwrap()) == Action::Announce as u32,
+        "unexpected action in announce response"
+    );
+    anyhow::ensure!(
+        u32::from_be_bytes(buf[4..8].try_into().unwrap()) == transaction_id,
+        "transaction_id mismatch in announce response"
+    );
+
+    let interval = u32::from_be_bytes(buf[8..12].try_into().unwrap());
+    let leechers = u32::from_be_bytes(buf[12..16].try_into().unwrap());
+    let seeders = u32::from_be_bytes(buf[16..20].try_into().unwrap());
+    let peers = buf[20..]
+        .chunks_exact(6)
+        .map(|chunk| {
+            let ip = std::net::Ipv4Addr::new(chunk[0], chunk[1], chunk[2], chunk[3]);
+            let port = u16::from_be_bytes([chunk[4], chunk[5]]);
+            SocketAddr::from((ip, port))
+        })
+        .collect();
+
+    Ok(UdpAnnounceResponse {
+        interval,
+        leechers,
+        seeders,
+        peers,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn announce(
+    socket: &UdpSocket,
+    tracker_addr: SocketAddr,
+    connection_id: u64,
+    info_hash: Id20,
+    peer_id: Id20,
+    downloaded: u64,
+    left: u64,
+    uploaded: u64,
+    event: Option<TrackerRequestEvent>,
+    key: u32,
+    port: u16,
+) -> anyhow::Result<UdpAnnounceResponse> {
+    let transaction_id: u32 = rand::random();
+    let packet = build_announce_request(
+        transaction_id,
+        connection_id,
+        info_hash,
+        peer_id,
+        downloaded,
+        left,
+        uploaded,
+        event,
+        key,
+        port,
+    );
+
+    // Room for up to ~340 compact peers, which is more than any tracker realistically returns.
+    let mut buf = [0u8; 20 + 6 * 340];
+    let size = send_and_receive(socket, tracker_addr, &packet, &mut buf).await?;
+    parse_announce_response(&buf[..size], transaction_id)
+        .with_context(|| format!("in announce response from {}", tracker_addr))
+}
+
+fn build_scrape_request(transaction_id: u32, connection_id: u64, info_hashes: &[Id20]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(16 + info_hashes.len() * 20);
+    packet.extend_from_slice(&connection_id.to_be_bytes());
+    packet.extend_from_slice(&(Action::Scrape as u32).to_be_bytes());
+    packet.extend_from_slice(&transaction_id.to_be_bytes());
+    for info_hash in info_hashes {
+        packet.extend_from_slice(&info_hash.0);
+    }
+    packet
+}
+
+fn parse_scrape_response(buf: &[u8], transaction_id: u32) -> anyhow::Result<Vec<ScrapeInfo>> {
+    anyhow::ensure!(buf.len() >= 8, "scrape response too short");
+    anyhow::ensure!(
+        u32::from_be_bytes(buf[0..4].try_into().unwrap()) == Action::Scrape as u32,
+        "unexpected action in scrape response"
+    );
+    anyhow::ensure!(
+        u32::from_be_bytes(buf[4..8].try_into().unwrap()) == transaction_id,
+        "transaction_id mismatch in scrape response"
+    );
+
+    Ok(buf[8..]
+        .chunks_exact(12)
+        .map(|chunk| ScrapeInfo {
+            seeders: u32::from_be_bytes(chunk[0..4].try_into().unwrap()),
+            completed: u32::from_be_bytes(chunk[4..8].try_into().unwrap()),
+            leechers: u32::from_be_bytes(chunk[8..12].try_into().unwrap()),
+        })
+        .collect())
+}
+
+async fn scrape(
+    socket: &UdpSocket,
+    tracker_addr: SocketAddr,
+    connection_id: u64,
+    info_hashes: &[Id20],
+) -> anyhow::Result<Vec<ScrapeInfo>> {
+    let info_hashes = if info_hashes.len() > MAX_SCRAPE_INFO_HASHES {
+        &info_hashes[..MAX_SCRAPE_INFO_HASHES]
+    } else {
+        info_hashes
+    };
+    anyhow::ensure!(!info_hashes.is_empty(), "scrape requires at least one info_hash");
+
+    let transaction_id: u32 = rand::random();
+    let packet = build_scrape_request(transaction_id, connection_id, info_hashes);
+
+    let mut buf = vec![0u8; 8 + info_hashes.len() * 12];
+    let size = send_and_receive(socket, tracker_addr, &packet, &mut buf).await?;
+    parse_scrape_response(&buf[..size], transaction_id)
+        .with_context(|| format!("in scrape response from {}", tracker_addr))
+}
+
+/// Keeps the BEP 15 connection handshake alive across repeated announces to the same tracker,
+/// so callers don't have to re-connect on every announce interval.
+pub struct UdpTrackerClient {
+    socket: UdpSocket,
+    tracker_addr: SocketAddr,
+    connection: Option<(u64, tokio::time::Instant)>,
+}
+
+impl UdpTrackerClient {
+    pub async fn new(tracker_addr: SocketAddr) -> anyhow::Result<Self> {
+        let bind_addr = if tracker_addr.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind_addr).await?;
+        // Filters incoming datagrams to this tracker at the kernel level, so a spoofed reply
+        // from another host (trivial to send given our ephemeral port and the 32-bit
+        // transaction_id) is dropped instead of being treated as this client's response.
+        socket.connect(tracker_addr).await?;
+        Ok(Self {
+            socket,
+            tracker_addr,
+            connection: None,
+        })
+    }
+
+    async fn connection_id(&mut self) -> anyhow::Result<u64> {
+        if let Some((id, obtained_at)) = self.connection {
+            if obtained_at.elapsed() < CONNECTION_ID_TTL {
+                return Ok(id);
+            }
+        }
+        let id = connect(&self.socket, self.tracker_addr).await?;
+        self.connection = Some((id, tokio::time::Instant::now()));
+        Ok(id)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn announce(
+        &mut self,
+        info_hash: Id20,
+        peer_id: Id20,
+        downloaded: u64,
+        left: u64,
+        uploaded: u64,
+        event: Option<TrackerRequestEvent>,
+        key: u32,
+        port: u16,
+    ) -> anyhow::Result<UdpAnnounceResponse> {
+        let connection_id = self.connection_id().await?;
+        match announce(
+            &self.socket,
+            self.tracker_addr,
+            connection_id,
+            info_hash,
+            peer_id,
+            downloaded,
+            left,
+            uploaded,
+            event,
+            key,
+            port,
+        )
+        .await
+        {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                // The connection_id might have expired under us; drop it so the next call
+                // re-connects instead of retrying with a connection_id the tracker will reject.
+                self.connection = None;
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn scrape(&mut self, info_hashes: &[Id20]) -> anyhow::Result<Vec<ScrapeInfo>> {
+        let connection_id = self.connection_id().await?;
+        match scrape(&self.socket, self.tracker_addr, connection_id, info_hashes).await {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                self.connection = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retransmit_timeout_follows_15_times_2_to_the_n() {
+        assert_eq!(retransmit_timeout(0), Duration::from_secs(15));
+        assert_eq!(retransmit_timeout(1), Duration::from_secs(30));
+        assert_eq!(retransmit_timeout(8), Duration::from_secs(15 * 256));
+    }
+
+    #[test]
+    fn connect_request_response_roundtrip() {
+        let transaction_id = 0x1234_5678;
+        let request = build_connect_request(transaction_id);
+        assert_eq!(request.len(), 16);
+        assert_eq!(&request[0..8], &PROTOCOL_ID.to_be_bytes());
+        assert_eq!(&request[8..12], &(Action::Connect as u32).to_be_bytes());
+        assert_eq!(&request[12..16], &transaction_id.to_be_bytes());
+
+        let mut response = Vec::new();
+        response.extend_from_slice(&(Action::Connect as u32).to_be_bytes());
+        response.extend_from_slice(&transaction_id.to_be_bytes());
+        response.extend_from_slice(&0xdead_beef_0011_2233u64.to_be_bytes());
+
+        let connection_id = parse_connect_response(&response, transaction_id).unwrap();
+        assert_eq!(connection_id, 0xdead_beef_0011_2233);
+    }
+
+    #[test]
+    fn connect_response_rejects_wrong_action_and_transaction_id() {
+        let transaction_id = 42;
+        let mut wrong_action = Vec::new();
+        wrong_action.extend_from_slice(&(Action::Announce as u32).to_be_bytes());
+        wrong_action.extend_from_slice(&transaction_id.to_be_bytes());
+        wrong_action.extend_from_slice(&0u64.to_be_bytes());
+        assert!(parse_connect_response(&wrong_action, transaction_id).is_err());
+
+        let mut wrong_transaction = Vec::new();
+        wrong_transaction.extend_from_slice(&(Action::Connect as u32).to_be_bytes());
+        wrong_transaction.extend_from_slice(&(transaction_id + 1).to_be_bytes());
+        wrong_transaction.extend_from_slice(&0u64.to_be_bytes());
+        assert!(parse_connect_response(&wrong_transaction, transaction_id).is_err());
+    }
+
+    #[test]
+    fn connect_response_rejects_truncated_buffer() {
+        assert!(parse_connect_response(&[0u8; 15], 0).is_err());
+    }
+
+    #[test]
+    fn announce_request_response_roundtrip() {
+        let transaction_id = 7;
+        let info_hash = Id20::new([1u8; 20]);
+        let peer_id = Id20::new([2u8; 20]);
+        let request = build_announce_request(
+            transaction_id,
+            0x1122_3344_5566_7788,
+            info_hash,
+            peer_id,
+            100,
+            200,
+            300,
+            Some(TrackerRequestEvent::Started),
+            0xaabb_ccdd,
+            6881,
+        );
+        assert_eq!(request.len(), 98);
+        assert_eq!(&request[0..8], &0x1122_3344_5566_7788u64.to_be_bytes());
+        assert_eq!(&request[8..12], &(Action::Announce as u32).to_be_bytes());
+        assert_eq!(&request[12..16], &transaction_id.to_be_bytes());
+        assert_eq!(&request[16..36], &info_hash.0);
+        assert_eq!(&request[36..56], &peer_id.0);
+        assert_eq!(&request[56..64], &100u64.to_be_bytes());
+        assert_eq!(&request[64..72], &200u64.to_be_bytes());
+        assert_eq!(&request[72..80], &300u64.to_be_bytes());
+        assert_eq!(&request[80..84], &2u32.to_be_bytes()); // Started.
+        assert_eq!(&request[84..88], &0u32.to_be_bytes()); // IP.
+        assert_eq!(&request[88..92], &0xaabb_ccddu32.to_be_bytes());
+        assert_eq!(&request[92..96], &(-1i32).to_be_bytes()); // num_want.
+        assert_eq!(&request[96..98], &6881u16.to_be_bytes());
+
+        let mut response = Vec::new();
+        response.extend_from_slice(&(Action::Announce as u32).to_be_bytes());
+        response.extend_from_slice(&transaction_id.to_be_bytes());
+        response.extend_from_slice(&1800u32.to_be_bytes()); // interval
+        response.extend_from_slice(&3u32.to_be_bytes()); // leechers
+        response.extend_from_slice(&5u32.to_be_bytes()); // seeders
+        response.extend_from_slice(&[127, 0, 0, 1, 0x1a, 0xe1]); // 127.0.0.1:6881
+        response.extend_from_slice(&[10, 0, 0, 2, 0x1a, 0xe2]); // 10.0.0.2:6882
+
+        let parsed = parse_announce_response(&response, transaction_id).unwrap();
+        assert_eq!(parsed.interval, 1800);
+        assert_eq!(parsed.leechers, 3);
+        assert_eq!(parsed.seeders, 5);
+        assert_eq!(
+            parsed.peers,
+            vec![
+                SocketAddr::from(([127, 0, 0, 1], 6881)),
+                SocketAddr::from(([10, 0, 0, 2], 6882)),
+            ]
+        );
+    }
+
+    #[test]
+    fn announce_response_rejects_truncated_buffer() {
+        assert!(parse_announce_response(&[0u8; 19], 0).is_err());
+    }
+
+    #[test]
+    fn announce_response_ignores_trailing_partial_peer() {
+        let transaction_id = 1;
+        let mut response = Vec::new();
+        response.extend_from_slice(&(Action::Announce as u32).to_be_bytes());
+        response.extend_from_slice(&transaction_id.to_be_bytes());
+        response.extend_from_slice(&0u32.to_be_bytes());
+        response.extend_from_slice(&0u32.to_be_bytes());
+        response.extend_from_slice(&0u32.to_be_bytes());
+        response.extend_from_slice(&[127, 0, 0, 1, 0x1a, 0xe1]);
+        response.extend_from_slice(&[1, 2, 3]); // truncated trailing peer, dropped by chunks_exact.
+
+        let parsed = parse_announce_response(&response, transaction_id).unwrap();
+        assert_eq!(parsed.peers, vec![SocketAddr::from(([127, 0, 0, 1], 6881))]);
+    }
+
+    #[test]
+    fn scrape_request_response_roundtrip() {
+        let transaction_id = 99;
+        let connection_id = 0x0102_0304_0506_0708;
+        let info_hashes = [Id20::new([3u8; 20]), Id20::new([4u8; 20])];
+        let request = build_scrape_request(transaction_id, connection_id, &info_hashes);
+        assert_eq!(request.len(), 16 + 40);
+        assert_eq!(&request[0..8], &connection_id.to_be_bytes());
+        assert_eq!(&request[8..12], &(Action::Scrape as u32).to_be_bytes());
+        assert_eq!(&request[12..16], &transaction_id.to_be_bytes());
+        assert_eq!(&request[16..36], &info_hashes[0].0);
+        assert_eq!(&request[36..56], &info_hashes[1].0);
+
+        let mut response = Vec::new();
+        response.extend_from_slice(&(Action::Scrape as u32).to_be_bytes());
+        response.extend_from_slice(&transaction_id.to_be_bytes());
+        response.extend_from_slice(&10u32.to_be_bytes()); // seeders
+        response.extend_from_slice(&20u32.to_be_bytes()); // completed
+        response.extend_from_slice(&5u32.to_be_bytes()); // leechers
+
+        let parsed = parse_scrape_response(&response, transaction_id).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].seeders, 10);
+        assert_eq!(parsed[0].completed, 20);
+        assert_eq!(parsed[0].leechers, 5);
+    }
+
+    #[test]
+    fn scrape_response_rejects_truncated_buffer() {
+        assert!(parse_scrape_response(&[0u8; 7], 0).is_err());
+    }
+}